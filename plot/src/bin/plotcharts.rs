@@ -14,6 +14,7 @@ use plotters::prelude::*;
 use plotters::style::text_anchor::{HPos, Pos, VPos};
 use polars::lazy::dsl::{Expr, StrptimeOptions};
 use polars::lazy::prelude::*;
+use polars::prelude::Duration as PolarsDuration;
 use polars::prelude::PolarsError::{ComputeError, NoData};
 use polars::prelude::*;
 use std::ffi::OsString;
@@ -43,8 +44,34 @@ const COLOR_PALETTE: [RGBColor; 6] = [
     plotters::style::colors::full_palette::DEEPORANGE,
 ];
 
+// 標準大気圧 [Pa]
+const STANDARD_ATMOSPHERIC_PRESSURE_PA: f64 = 101325.0;
+
+// 飽和水蒸気圧 es(T) [Pa] (Tetensの式)
+fn saturation_vapor_pressure_pa(t_celsius: f64) -> f64 {
+    611.2 * ((17.67 * t_celsius) / (t_celsius + 243.5)).exp()
+}
+
+// 絶対湿度比(humidity ratio) W = 0.622*e/(P-e) [kg/kg]
+fn humidity_ratio(t_celsius: f64, relative_humidity_percent: f64, pressure_pa: f64) -> f64 {
+    let es = saturation_vapor_pressure_pa(t_celsius);
+    let e = relative_humidity_percent / 100.0 * es;
+    0.622 * e / (pressure_pa - e)
+}
+
+// 欠測値を表すセンチネル値を持ちうる数値列
+const NUMERIC_MEASUREMENT_COLUMNS: [&str; 7] = [
+    colname::TEMPERATURE,
+    colname::RELATIVE_HUMIDITY,
+    colname::ABSOLUTE_HUMIDITY,
+    colname::PRESSURE,
+    colname::TVOC,
+    colname::ECO2,
+    colname::CO2,
+];
+
 // CSVファイルを読み込んでデータフレームを作る
-fn read_csv<P: AsRef<Path>>(path: P) -> Result<LazyFrame, PolarsError> {
+fn read_csv<P: AsRef<Path>>(path: P, na_values: &[f64]) -> Result<LazyFrame, PolarsError> {
     let ldf = LazyCsvReader::new(path).has_header(true).finish()?;
 
     // measured_at列をstr型からdatetime型に変換する
@@ -60,7 +87,20 @@ fn read_csv<P: AsRef<Path>>(path: P) -> Result<LazyFrame, PolarsError> {
         )
         .alias(colname::MEASURED_AT); // 変換済みの列で上書きする
 
-    Ok(ldf.with_column(expr))
+    let mut ldf = ldf.with_column(expr);
+    // 欠測値のセンチネル(例: 999.9)をnullに置き換える
+    for na_value in na_values {
+        for column_name in NUMERIC_MEASUREMENT_COLUMNS {
+            ldf = ldf.with_column(
+                when(col(column_name).eq(lit(*na_value)))
+                    .then(lit(NULL))
+                    .otherwise(col(column_name))
+                    .alias(column_name),
+            );
+        }
+    }
+
+    Ok(ldf)
 }
 
 // X軸の日付時間
@@ -114,7 +154,8 @@ fn line_chart<'a, DB: DrawingBackend>(
     >,
     dataset: Vec<(&NaiveDateTime, &f64)>,
     sensor_id: &str,
-    line_style: Box<ShapeStyle>,
+    line_style: ShapeStyle,
+    y_desc: &str,
 ) -> anyhow::Result<()>
 where
     DB::ErrorType: 'static,
@@ -124,19 +165,37 @@ where
         dataset
             .iter()
             .copied()
-            .map(|(x, y)| Circle::new((*x, *y), 1, *line_style)),
+            .map(|(x, y)| Circle::new((*x, *y), 1, line_style)),
     )?;
     // 折れ線で表現する
+    let style = line_style;
     (*chart)
         .draw_series(LineSeries::new(
             dataset
                 .iter()
                 .copied()
                 .map(|(datetime, value)| (*datetime, *value)),
-            *line_style,
+            style,
         ))?
         .label(sensor_id)
-        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], *line_style));
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+    // 最大値にマーカーとラベルを付ける
+    if let Some((peak_x, peak_y)) = dataset
+        .iter()
+        .copied()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+    {
+        (*chart).draw_series(std::iter::once(Circle::new(
+            (*peak_x, *peak_y),
+            4,
+            style.color.filled(),
+        )))?;
+        (*chart).draw_series(std::iter::once(Text::new(
+            format!("{:.1}{} @ {}", peak_y, y_desc, peak_x.format("%H:%M")),
+            (*peak_x, *peak_y),
+            ("sans-serif", 12).into_font(),
+        )))?;
+    }
 
     Ok(())
 }
@@ -227,7 +286,8 @@ where
             &mut chart,
             itertools::izip!(&datetimes, &values).collect(),
             sensor_id,
-            Box::new(COLOR_PALETTE.get(index).unwrap_or(&COLOR_PALETTE[0]).into()),
+            COLOR_PALETTE.get(index).unwrap_or(&COLOR_PALETTE[0]).into(),
+            y_desc,
         )?;
     }
     // 凡例
@@ -240,14 +300,420 @@ where
     Ok(())
 }
 
+// 日次の最小・最大・平均に集計したトレンドグラフを作る
+fn plot_daily_trend<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    ldf: LazyFrame,
+    column_name: &str,
+    caption: &str,
+    y_desc: &str,
+    sensor_ids: &Vec<&str>,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    // Asia/Tokyoの日界に合わせるため9時間進めてから1日単位で集計する
+    let df = ldf
+        .select([
+            col(colname::SENSOR_ID),
+            col(colname::MEASURED_AT),
+            col(column_name),
+        ])
+        .filter(col(colname::SENSOR_ID).is_not_null())
+        .filter(col(colname::MEASURED_AT).is_not_null())
+        .filter(col(column_name).is_not_null())
+        .with_column(
+            col(colname::MEASURED_AT)
+                .dt()
+                .offset_by(lit("9h"))
+                .alias("tokyo_at"),
+        )
+        .groupby_dynamic(
+            [col(colname::SENSOR_ID)],
+            DynamicGroupOptions {
+                index_column: "tokyo_at".into(),
+                every: PolarsDuration::parse("1d"),
+                period: PolarsDuration::parse("1d"),
+                offset: PolarsDuration::parse("0d"),
+                closed_window: ClosedWindow::Left,
+                ..Default::default()
+            },
+        )
+        .agg([
+            col(column_name).min().alias("daily_min"),
+            col(column_name).max().alias("daily_max"),
+            col(column_name).mean().alias("daily_mean"),
+        ])
+        .sort("tokyo_at", SortOptions::default())
+        .collect()?;
+    // X軸の日付時間(既にAsia/Tokyoの日界にずらしてある)
+    let tokyo_at = df["tokyo_at"]
+        .datetime()?
+        .as_datetime_iter()
+        .collect::<Option<Vec<NaiveDateTime>>>()
+        .ok_or(ComputeError("datetime parse error".into()))?;
+    let day_start = tokyo_at
+        .iter()
+        .min()
+        .ok_or(anyhow!("value is empty"))?
+        .date();
+    let day_end = tokyo_at
+        .iter()
+        .max()
+        .ok_or(anyhow!("value is empty"))?
+        .date()
+        + Duration::days(1);
+    let range_datetime: Range<NaiveDateTime> = NaiveDateTime::new(
+        day_start,
+        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+    )..NaiveDateTime::new(day_end, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+    let range_datetime: RangedDateTime<NaiveDateTime> = range_datetime.into();
+    //
+    let ymin = df["daily_min"]
+        .f64()?
+        .min()
+        .ok_or(anyhow!("value is empty"))?;
+    let ymax = df["daily_max"]
+        .f64()?
+        .max()
+        .ok_or(anyhow!("value is empty"))?;
+    //
+    let mut chart = ChartBuilder::on(area)
+        .caption(caption, ("sans-serif", 16).into_font())
+        .margin(10)
+        .x_label_area_size(70)
+        .y_label_area_size(70)
+        .build_cartesian_2d(range_datetime, ymin..ymax)?;
+    //
+    let custom_x_label_formatter = |t: &NaiveDateTime| t.format("%Y-%m-%d").to_string();
+    chart
+        .configure_mesh()
+        .x_labels(10)
+        .x_label_style(
+            ("sans-serif", 11)
+                .into_font()
+                .transform(FontTransform::Rotate270)
+                .with_anchor::<RGBColor>(Pos::new(HPos::Right, VPos::Top)),
+        )
+        .x_label_formatter(&custom_x_label_formatter)
+        .set_tick_mark_size(LabelAreaPosition::Bottom, 20)
+        .y_desc(y_desc)
+        .draw()?;
+    //
+    for (index, sensor_id) in sensor_ids.iter().enumerate() {
+        let sensor_df = df
+            .clone()
+            .lazy()
+            .filter(col(colname::SENSOR_ID).eq(lit(*sensor_id)))
+            .sort("tokyo_at", SortOptions::default())
+            .collect()?;
+        //
+        if sensor_df[0].is_empty() {
+            continue;
+        }
+        let datetimes = sensor_df["tokyo_at"]
+            .datetime()?
+            .as_datetime_iter()
+            .collect::<Option<Vec<NaiveDateTime>>>()
+            .ok_or(ComputeError("datetime parse error".into()))?;
+        let daily_min = sensor_df["daily_min"]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        let daily_max = sensor_df["daily_max"]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        let daily_mean = sensor_df["daily_mean"]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        //
+        let color: RGBColor = *COLOR_PALETTE.get(index).unwrap_or(&COLOR_PALETTE[0]);
+        // 最小〜最大の範囲を帯で塗りつぶす
+        let band: Vec<(NaiveDateTime, f64)> = datetimes
+            .iter()
+            .zip(daily_min.iter())
+            .map(|(t, v)| (*t, *v))
+            .chain(
+                datetimes
+                    .iter()
+                    .rev()
+                    .zip(daily_max.iter().rev())
+                    .map(|(t, v)| (*t, *v)),
+            )
+            .collect();
+        chart.draw_series(std::iter::once(Polygon::new(band, color.mix(0.2))))?;
+        // 平均値を中心線で表す
+        chart
+            .draw_series(LineSeries::new(
+                itertools::izip!(&datetimes, &daily_mean).map(|(t, v)| (*t, *v)),
+                color,
+            ))?
+            .label(*sensor_id)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    // 凡例
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.5))
+        .border_style(BLACK)
+        .draw()?;
+
+    Ok(())
+}
+
+// 気温と相対湿度を左右2軸の重ね書きで見るグラフを作る
+fn plot_temperature_humidity_twin_axis<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    ldf: LazyFrame,
+    sensor_ids: &Vec<&str>,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let df = ldf
+        .select([
+            col(colname::SENSOR_ID),
+            col(colname::MEASURED_AT),
+            col(colname::TEMPERATURE),
+            col(colname::RELATIVE_HUMIDITY),
+        ])
+        .filter(col(colname::SENSOR_ID).is_not_null())
+        .filter(col(colname::MEASURED_AT).is_not_null())
+        .filter(col(colname::TEMPERATURE).is_not_null())
+        .filter(col(colname::RELATIVE_HUMIDITY).is_not_null())
+        .collect()?;
+    // X軸の日付時間
+    let (_, range_datetime) = as_datetime_vector(&df[colname::MEASURED_AT], Tokyo)?;
+    //
+    let temp_min = df[colname::TEMPERATURE]
+        .f64()?
+        .min()
+        .ok_or(anyhow!("value is empty"))?;
+    let temp_max = df[colname::TEMPERATURE]
+        .f64()?
+        .max()
+        .ok_or(anyhow!("value is empty"))?;
+    let humidity_min = df[colname::RELATIVE_HUMIDITY]
+        .f64()?
+        .min()
+        .ok_or(anyhow!("value is empty"))?;
+    let humidity_max = df[colname::RELATIVE_HUMIDITY]
+        .f64()?
+        .max()
+        .ok_or(anyhow!("value is empty"))?;
+    //
+    let mut chart = ChartBuilder::on(area)
+        .caption("temperature & humidity", ("sans-serif", 16).into_font())
+        .margin(10)
+        .x_label_area_size(70)
+        .y_label_area_size(70)
+        .right_y_label_area_size(70)
+        .build_cartesian_2d(range_datetime.clone(), temp_min..temp_max)?
+        .set_secondary_coord(range_datetime, humidity_min..humidity_max);
+    //
+    let custom_x_label_formatter = |t: &NaiveDateTime| {
+        if t.time().hour() == 0 {
+            t.format("%Y-%m-%d %a").to_string()
+        } else {
+            t.format("%H:%M:%S").to_string()
+        }
+    };
+    chart
+        .configure_mesh()
+        .x_labels(24)
+        .x_label_style(
+            ("sans-serif", 11)
+                .into_font()
+                .transform(FontTransform::Rotate270)
+                .with_anchor::<RGBColor>(Pos::new(HPos::Right, VPos::Top)),
+        )
+        .x_label_formatter(&custom_x_label_formatter)
+        .set_tick_mark_size(LabelAreaPosition::Bottom, 20)
+        .y_desc("C")
+        .draw()?;
+    chart.configure_secondary_axes().y_desc("%RH").draw()?;
+    //
+    for (index, sensor_id) in sensor_ids.iter().enumerate() {
+        let sensor_df = df
+            .clone()
+            .lazy()
+            .filter(col(colname::SENSOR_ID).eq(lit(*sensor_id)))
+            .collect()?;
+        //
+        if sensor_df[0].is_empty() {
+            continue;
+        }
+        let (datetimes, _) = as_datetime_vector(&sensor_df[colname::MEASURED_AT], Tokyo)?;
+        let temperatures = sensor_df[colname::TEMPERATURE]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        let humidities = sensor_df[colname::RELATIVE_HUMIDITY]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        //
+        let color: RGBColor = *COLOR_PALETTE.get(index).unwrap_or(&COLOR_PALETTE[0]);
+        // 気温は実線
+        chart
+            .draw_series(LineSeries::new(
+                itertools::izip!(&datetimes, &temperatures).map(|(t, v)| (*t, *v)),
+                color.stroke_width(2),
+            ))?
+            .label(format!("{} temperature", sensor_id))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        // 相対湿度は破線
+        chart
+            .draw_secondary_series(DashedLineSeries::new(
+                itertools::izip!(&datetimes, &humidities).map(|(t, v)| (*t, *v)),
+                5,
+                3,
+                color.stroke_width(1),
+            ))?
+            .label(format!("{} humidity", sensor_id))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    // 凡例
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.5))
+        .border_style(BLACK)
+        .draw()?;
+
+    Ok(())
+}
+
+// 空気線図(温度 x 絶対湿度比)を作る
+fn psychrometric_chart<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    ldf: LazyFrame,
+    sensor_ids: &Vec<&str>,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let df = ldf
+        .select([
+            col(colname::SENSOR_ID),
+            col(colname::TEMPERATURE),
+            col(colname::RELATIVE_HUMIDITY),
+            when(col(colname::PRESSURE).is_not_null())
+                .then(col(colname::PRESSURE) * lit(100.0))
+                .otherwise(lit(STANDARD_ATMOSPHERIC_PRESSURE_PA))
+                .alias("pressure_pa"),
+        ])
+        .filter(col(colname::SENSOR_ID).is_not_null())
+        .filter(col(colname::TEMPERATURE).is_not_null())
+        .filter(col(colname::RELATIVE_HUMIDITY).is_not_null())
+        .collect()?;
+    //
+    let temperatures = df[colname::TEMPERATURE]
+        .f64()?
+        .into_no_null_iter()
+        .collect::<Vec<f64>>();
+    let tmin = temperatures
+        .iter()
+        .cloned()
+        .fold(f64::INFINITY, f64::min)
+        .min(20.0);
+    let tmax = temperatures
+        .iter()
+        .cloned()
+        .fold(f64::NEG_INFINITY, f64::max)
+        .max(26.0);
+    //
+    let mut chart = ChartBuilder::on(area)
+        .caption("psychrometric chart", ("sans-serif", 16).into_font())
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(70)
+        .build_cartesian_2d(tmin..tmax, 0.0..0.03)?;
+    //
+    chart
+        .configure_mesh()
+        .x_desc("dry-bulb temperature (C)")
+        .y_desc("humidity ratio (kg/kg)")
+        .draw()?;
+    // 快適域(20-26C, 40-60%RH)を塗りつぶす
+    let comfort_corners: Vec<(f64, f64)> = [(20.0, 40.0), (26.0, 40.0), (26.0, 60.0), (20.0, 60.0)]
+        .iter()
+        .map(|(t, rh)| (*t, humidity_ratio(*t, *rh, STANDARD_ATMOSPHERIC_PRESSURE_PA)))
+        .collect();
+    chart.draw_series(std::iter::once(Polygon::new(
+        comfort_corners,
+        GREEN.mix(0.2),
+    )))?;
+    // 等相対湿度線(10%刻み)を描く
+    let t_steps = 50;
+    for rh in (10..=100).step_by(10) {
+        let curve: Vec<(f64, f64)> = (0..=t_steps)
+            .map(|i| {
+                let t = tmin + (tmax - tmin) * (i as f64) / (t_steps as f64);
+                (
+                    t,
+                    humidity_ratio(t, rh as f64, STANDARD_ATMOSPHERIC_PRESSURE_PA),
+                )
+            })
+            .collect();
+        chart.draw_series(LineSeries::new(curve, BLACK.mix(0.3)))?;
+    }
+    // センサー毎の実測点を散布図で描く
+    for (index, sensor_id) in sensor_ids.iter().enumerate() {
+        let sensor_df = df
+            .clone()
+            .lazy()
+            .filter(col(colname::SENSOR_ID).eq(lit(*sensor_id)))
+            .collect()?;
+        //
+        if sensor_df[0].is_empty() {
+            continue;
+        }
+        let temperatures = sensor_df[colname::TEMPERATURE]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        let humidities = sensor_df[colname::RELATIVE_HUMIDITY]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        let pressures = sensor_df["pressure_pa"]
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<f64>>();
+        //
+        let color: RGBColor = *COLOR_PALETTE.get(index).unwrap_or(&COLOR_PALETTE[0]);
+        chart
+            .draw_series(
+                itertools::izip!(&temperatures, &humidities, &pressures).map(|(t, rh, p)| {
+                    Circle::new((*t, humidity_ratio(*t, *rh, *p)), 2, color.filled())
+                }),
+            )?
+            .label(*sensor_id)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    // 凡例
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.5))
+        .border_style(BLACK)
+        .draw()?;
+
+    Ok(())
+}
+
 // グラフを作る
 fn plot_dataframe<DB: DrawingBackend>(
     root_area: DrawingArea<DB, plotters::coord::Shift>,
     ldf: LazyFrame,
+    daily_trend: bool,
 ) -> anyhow::Result<()>
 where
     DB::ErrorType: 'static,
 {
+    // 生の時系列を描くか、日次トレンドを描くか
+    let plot_fn = if daily_trend { plot_daily_trend } else { plot };
     // センサーIDを取り出す
     let sensor_id_df = ldf
         .clone()
@@ -259,12 +725,14 @@ where
 
     // 背景色
     root_area.fill(&WHITE)?;
-    // 縦に4分割する
+    // 縦に5分割する
     // 横に2分割する
-    let areas = root_area.split_evenly((4, 2));
-    if let [one_l, one_r, two_l, two_r, three_l, three_r, four_l, four_r] = &areas[..8] {
+    let areas = root_area.split_evenly((5, 2));
+    if let [one_l, one_r, two_l, two_r, three_l, three_r, four_l, four_r, five_l, _five_r] =
+        &areas[..10]
+    {
         // 気温グラフを作る
-        plot(
+        plot_fn(
             one_l,
             ldf.clone(),
             colname::TEMPERATURE,
@@ -273,7 +741,7 @@ where
             &sensor_ids,
         )?;
         // 気圧グラフを作る
-        plot(
+        plot_fn(
             one_r,
             ldf.clone(),
             colname::PRESSURE,
@@ -282,7 +750,7 @@ where
             &sensor_ids,
         )?;
         // 相対湿度グラフを作る
-        plot(
+        plot_fn(
             two_l,
             ldf.clone(),
             colname::RELATIVE_HUMIDITY,
@@ -291,7 +759,7 @@ where
             &sensor_ids,
         )?;
         // 絶対湿度グラフを作る
-        plot(
+        plot_fn(
             two_r,
             ldf.clone(),
             colname::ABSOLUTE_HUMIDITY,
@@ -300,7 +768,7 @@ where
             &sensor_ids,
         )?;
         // 二酸化炭素濃度グラフを作る
-        plot(
+        plot_fn(
             three_l,
             ldf.clone(),
             colname::CO2,
@@ -309,7 +777,7 @@ where
             &sensor_ids,
         )?;
         // Total VOCグラフを作る
-        plot(
+        plot_fn(
             three_r,
             ldf.clone(),
             colname::TVOC,
@@ -318,14 +786,18 @@ where
             &sensor_ids,
         )?;
         // 二酸化炭素相当量グラフを作る
-        plot(
+        plot_fn(
             four_l,
-            ldf,
+            ldf.clone(),
             colname::ECO2,
             "equivalent CO2",
             "ppm",
             &sensor_ids,
         )?;
+        // 空気線図を作る
+        psychrometric_chart(four_r, ldf.clone(), &sensor_ids)?;
+        // 気温と相対湿度の重ね書きグラフを作る
+        plot_temperature_humidity_twin_axis(five_l, ldf, &sensor_ids)?;
     } else {
         panic!("fatal error")
     }
@@ -335,48 +807,245 @@ where
     Ok(())
 }
 
+// ヘッドレス環境でファイルを使わずに確認できるASCIIアートの描画バックエンド
+mod console_backend {
+    use plotters::backend::DrawingBackend;
+    use plotters_backend::{BackendColor, BackendCoord, DrawingErrorKind};
+    use std::error::Error;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub struct TextDrawingBackendError;
+
+    impl fmt::Display for TextDrawingBackendError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "text drawing backend error")
+        }
+    }
+
+    impl Error for TextDrawingBackendError {}
+
+    // 端末の桁数x行数の文字バッファ
+    pub struct TextDrawingBackend(pub Vec<Vec<char>>);
+
+    impl TextDrawingBackend {
+        pub fn new(columns: u32, rows: u32) -> Self {
+            TextDrawingBackend(vec![vec![' '; columns as usize]; rows as usize])
+        }
+    }
+
+    impl DrawingBackend for TextDrawingBackend {
+        type ErrorType = TextDrawingBackendError;
+
+        fn get_size(&self) -> (u32, u32) {
+            (self.0[0].len() as u32, self.0.len() as u32)
+        }
+
+        fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+            Ok(())
+        }
+
+        fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+            for row in self.0.iter() {
+                println!("{}", row.iter().collect::<String>());
+            }
+            Ok(())
+        }
+
+        fn draw_pixel(
+            &mut self,
+            pos: BackendCoord,
+            color: BackendColor,
+        ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+            if pos.0 < 0 || pos.1 < 0 {
+                return Ok(());
+            }
+            let (x, y) = (pos.0 as usize, pos.1 as usize);
+            if y >= self.0.len() || x >= self.0[0].len() {
+                return Ok(());
+            }
+            if color.alpha > 0.3 {
+                self.0[y][x] = match color.rgb {
+                    (0, 0, 0) => '#',
+                    _ => '*',
+                };
+            }
+            Ok(())
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum ChartFileType {
     Png,
     Svg,
+    Console,
+}
+
+// 端末の桁数と行数を得る(TIOCGWINSZで標準出力のウィンドウサイズを問い合わせる。取得できなければ既定値)
+fn terminal_size_or_default() -> (u32, u32) {
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(columns), terminal_size::Height(rows))) => {
+            (columns as u32, rows as u32)
+        }
+        None => (120, 40),
+    }
 }
 
-// csvファイルからグラフを作る
+// 出力先の種類に応じてグラフを描画する(コンソールの場合はファイルに書かず標準出力へ描く)
+fn render_chart(
+    chart_file_type: ChartFileType,
+    outfilepath: &Path,
+    plotareasize: (u32, u32),
+    ldf: LazyFrame,
+    daily_trend: bool,
+) -> anyhow::Result<()> {
+    match chart_file_type {
+        ChartFileType::Png => {
+            let root_area = BitMapBackend::new(outfilepath, plotareasize).into_drawing_area();
+            plot_dataframe(root_area, ldf, daily_trend)
+        }
+        ChartFileType::Svg => {
+            let root_area = SVGBackend::new(outfilepath, plotareasize).into_drawing_area();
+            plot_dataframe(root_area, ldf, daily_trend)
+        }
+        ChartFileType::Console => {
+            let (columns, rows) = terminal_size_or_default();
+            let root_area =
+                console_backend::TextDrawingBackend::new(columns, rows).into_drawing_area();
+            plot_dataframe(root_area, ldf, daily_trend)
+        }
+    }
+}
+
+// 末尾に"_{日数}d"を付けたファイル名にする(例: name.png -> name_7d.png)
+fn windowed_outfilepath(outfilepath: &Path, window_days: u32) -> PathBuf {
+    let stem = outfilepath
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut new_name = format!("{}_{}d", stem, window_days);
+    if let Some(extension) = outfilepath.extension() {
+        new_name.push('.');
+        new_name.push_str(&extension.to_string_lossy());
+    }
+    outfilepath.with_file_name(new_name)
+}
+
+// csvファイルからグラフを作る。windowsが指定されていれば、最新日時からのトレーリングウィンドウ毎に分けて作る
 fn run<P: AsRef<Path>>(
     infilepath: P,
     overwrite: bool,
     plotareasize: (u32, u32),
     chart_file_type: ChartFileType,
-) -> anyhow::Result<String> {
+    daily_trend: bool,
+    na_values: &[f64],
+    windows: &[u32],
+) -> anyhow::Result<(String, Vec<PathBuf>)> {
     // 出力するファイル名は入力ファイルの.csvを.png/.svgに変えたもの
     let infilepath_string = format!("{:?}", infilepath.as_ref().as_os_str());
     let mut outfilepath: PathBuf = PathBuf::from(infilepath.as_ref());
     outfilepath.set_extension(match chart_file_type {
         ChartFileType::Png => "png",
         ChartFileType::Svg => "svg",
+        ChartFileType::Console => "",
     });
-    // 出力するファイルの存在確認
-    if outfilepath.is_file() && !overwrite {
-        let outfilepath_string = format!("{:?}", outfilepath.as_os_str());
-        Err(anyhow!("{} file is already exist!", outfilepath_string))?;
-    }
     // CSVファイルからデーターフレームを作る
-    let df: DataFrame = read_csv(infilepath)?
+    let df: DataFrame = read_csv(infilepath, na_values)?
         .sort(colname::MEASURED_AT, SortOptions::default())
         .collect()?;
     //
-    match chart_file_type {
-        ChartFileType::Png => {
-            let root_area = BitMapBackend::new(&outfilepath, plotareasize).into_drawing_area();
-            plot_dataframe(root_area, df.clone().lazy())?;
+    let mut produced_files: Vec<PathBuf> = Vec::new();
+    if windows.is_empty() {
+        // 出力するファイルの存在確認(コンソールへはファイルを書かないので確認不要)
+        if chart_file_type != ChartFileType::Console && outfilepath.is_file() && !overwrite {
+            let outfilepath_string = format!("{:?}", outfilepath.as_os_str());
+            Err(anyhow!("{} file is already exist!", outfilepath_string))?;
         }
-        ChartFileType::Svg => {
-            let root_area = SVGBackend::new(&outfilepath, plotareasize).into_drawing_area();
-            plot_dataframe(root_area, df.clone().lazy())?;
+        render_chart(
+            chart_file_type,
+            &outfilepath,
+            plotareasize,
+            df.clone().lazy(),
+            daily_trend,
+        )?;
+        if chart_file_type != ChartFileType::Console {
+            produced_files.push(outfilepath);
         }
-    };
+    } else {
+        // 最新の測定日時を基準にする
+        let latest_measured_at = df[colname::MEASURED_AT]
+            .datetime()?
+            .as_datetime_iter()
+            .collect::<Option<Vec<NaiveDateTime>>>()
+            .ok_or(anyhow!("datetime parse error"))?
+            .into_iter()
+            .max()
+            .ok_or(anyhow!("value is empty"))?;
+        let window_outfilepaths: Vec<PathBuf> = windows
+            .iter()
+            .map(|window_days| windowed_outfilepath(&outfilepath, *window_days))
+            .collect();
+        // 出力するファイルの存在確認(1枚でも既存ならどのウィンドウも描画せずに中断する)
+        if chart_file_type != ChartFileType::Console {
+            for window_outfilepath in &window_outfilepaths {
+                if window_outfilepath.is_file() && !overwrite {
+                    let outfilepath_string = format!("{:?}", window_outfilepath.as_os_str());
+                    Err(anyhow!("{} file is already exist!", outfilepath_string))?;
+                }
+            }
+        }
+        for (window_days, window_outfilepath) in windows.iter().zip(window_outfilepaths) {
+            let cutoff_datetime = latest_measured_at - Duration::days(*window_days as i64);
+            let windowed_ldf = df.clone().lazy().filter(
+                col(colname::MEASURED_AT).gt_eq(
+                    lit(cutoff_datetime.and_utc().timestamp_millis())
+                        .cast(DataType::Datetime(TimeUnit::Milliseconds, None)),
+                ),
+            );
+            render_chart(
+                chart_file_type,
+                &window_outfilepath,
+                plotareasize,
+                windowed_ldf,
+                daily_trend,
+            )?;
+            if chart_file_type != ChartFileType::Console {
+                produced_files.push(window_outfilepath);
+            }
+        }
+    }
     // 結果を返す
-    Ok(format!("inputfile -> {}\n{:?}", infilepath_string, df))
+    Ok((
+        format!("inputfile -> {}\n{:?}", infilepath_string, df),
+        produced_files,
+    ))
+}
+
+// 生成した画像ファイルをcsvファイル毎にまとめたindex.htmlを作る
+fn write_index_html(outdir: &Path, entries: &[(PathBuf, Vec<PathBuf>)]) -> anyhow::Result<PathBuf> {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>Telemetry charts</title>\n</head>\n<body>\n");
+    for (infilepath, images) in entries {
+        if images.is_empty() {
+            continue;
+        }
+        html.push_str(&format!("<h2>{}</h2>\n", infilepath.display()));
+        for image in images {
+            let file_name = image
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<a href=\"{0}\"><img src=\"{0}\" alt=\"{0}\"></a>\n",
+                file_name
+            ));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    let index_path = outdir.join("index.html");
+    fs::write(&index_path, html)?;
+    Ok(index_path)
 }
 
 #[derive(Parser)]
@@ -389,7 +1058,15 @@ struct Cli {
     #[arg(long)]
     png: bool,
     #[arg(long)]
+    console: bool,
+    #[arg(long)]
     overwrite: bool,
+    #[arg(long)]
+    trend: bool,
+    #[arg(long = "na-value")]
+    na_values: Vec<f64>,
+    #[arg(long, value_delimiter = ',')]
+    windows: Vec<u32>,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -408,7 +1085,9 @@ fn main() -> anyhow::Result<()> {
         })
         .collect();
     // 出力ファイルの種類
-    let chart_file_type = if cli.png {
+    let chart_file_type = if cli.console {
+        ChartFileType::Console
+    } else if cli.png {
         ChartFileType::Png
     } else {
         ChartFileType::Svg
@@ -416,10 +1095,26 @@ fn main() -> anyhow::Result<()> {
     // グラフの大きさ
     let plotareasize = (cli.width, cli.height);
     // csvファイルからグラフを作る
+    let mut index_entries: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
     for p in csv_files {
-        let result = run(p.path(), cli.overwrite, plotareasize, chart_file_type)
-            .unwrap_or_else(|e| format!("{:?}", e));
-        println!("{}", result);
+        let infilepath = p.path();
+        let result = run(
+            &infilepath,
+            cli.overwrite,
+            plotareasize,
+            chart_file_type,
+            cli.trend,
+            &cli.na_values,
+            &cli.windows,
+        )
+        .unwrap_or_else(|e| (format!("{:?}", e), Vec::new()));
+        println!("{}", result.0);
+        index_entries.push((infilepath, result.1));
+    }
+    // --windowsが指定されている場合は、生成した画像をまとめたindex.htmlを作る
+    if !cli.windows.is_empty() {
+        let index_path = write_index_html(Path::new("./"), &index_entries)?;
+        println!("index -> {:?}", index_path.as_os_str());
     }
 
     Ok(())